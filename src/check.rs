@@ -0,0 +1,84 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use log::info;
+
+use crate::panel;
+
+/// Recursively walks `dir` (the live uploader lays batches out in dated
+/// `YYYY_MM_DD/` subdirectories, not flat), runs [`panel::parse_xml`] on
+/// every `.xml` file found, and prints a per-file OK/FAIL summary plus an
+/// overall board-level PASS/FAIL tally.
+///
+/// This gives operators a fast pre-flight validation of a batch before it
+/// is committed, and a way to regression-test schema changes against a
+/// corpus of captured files. Returns `Ok(true)` only if every file parsed
+/// cleanly - a clean parse containing failed boards still counts as OK
+/// here, since that's a genuine AOI/AXI result, not a parsing problem.
+pub fn check(dir: &Path, line: &str) -> Result<bool> {
+    let mut checked = 0usize;
+    let mut passed = 0usize;
+    let mut boards_passed = 0usize;
+    let mut boards_failed = 0usize;
+    let mut all_ok = true;
+
+    for path in collect_xml_files(dir)? {
+        checked += 1;
+        match panel::parse_xml(
+            &path,
+            line,
+            &panel::StationProfile::REPAIR,
+            &panel::StationProfile::AOI_AXI,
+        ) {
+            Ok(p) => {
+                passed += 1;
+                let (file_passed, file_failed) = tally_boards(&p);
+                boards_passed += file_passed;
+                boards_failed += file_failed;
+                println!(
+                    "OK   {} ({} board(s), {file_passed} passed, {file_failed} failed)",
+                    path.display(),
+                    p.Boards.len()
+                );
+            }
+            Err(diagnostics) => {
+                all_ok = false;
+                println!("FAIL {}", path.display());
+                for d in &diagnostics {
+                    println!("       {d}");
+                }
+            }
+        }
+    }
+
+    info!("Checked {checked} file(s), {passed} passed.");
+    println!("{passed}/{checked} file(s) passed.");
+    println!("{boards_passed} board(s) passed, {boards_failed} board(s) failed.");
+
+    Ok(all_ok)
+}
+
+/// Counts of boards whose `Result` is `"PASS"` vs anything else, mirroring
+/// the marker the uploader itself checks.
+fn tally_boards(panel: &panel::Panel) -> (usize, usize) {
+    let passed = panel.Boards.iter().filter(|b| b.Result == "PASS").count();
+    (passed, panel.Boards.len() - passed)
+}
+
+/// Recursively collects every `.xml`/`.XML` file under `dir`.
+fn collect_xml_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+
+        if path.is_dir() {
+            files.extend(collect_xml_files(&path)?);
+        } else if path.extension().is_some_and(|e| e == "xml" || e == "XML") {
+            files.push(path);
+        }
+    }
+
+    Ok(files)
+}
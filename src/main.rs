@@ -4,14 +4,36 @@ use anyhow::{bail, Result};
 use chrono::{DateTime, Local};
 use log::{debug, error, info, warn};
 use std::{
-    fs, path::{Path, PathBuf}, sync::mpsc::{self, SyncSender}, time::Duration
+    fs, path::{Path, PathBuf}, sync::mpsc::{self, SyncSender}, sync::Arc, time::Duration
 };
+use notify::{RecursiveMode, Watcher};
+use notify_rust::Notification;
+use signal_hook::consts::{SIGINT, SIGTERM};
+use signal_hook::iterator::Signals;
 use tiberius::{Client, Query};
-use tokio::{net::TcpStream, time::sleep};
+use tokio::{net::TcpStream, sync::mpsc as mpsc_tokio, time::sleep};
 use tokio_util::compat::TokioAsyncWriteCompatExt;
 use tray_item::{IconSource, TrayItem};
 
+mod check;
 mod panel;
+mod retry;
+mod spool;
+mod status;
+
+/// Line-delimited spool file that parsed panels are appended to when an
+/// upload chunk fails and its rows can't be written to the retry queue
+/// either, so they aren't lost while the SQL server is down.
+/// Spooling (like `retry.rs`'s queue) isn't behind an optional feature -
+/// `serde` is a required dependency of this crate, not a toggle, so there's
+/// no build configuration where a failed chunk has nowhere durable to go.
+const SPOOL_FILE: &str = "spool.jsonl";
+
+/// Embedded SQLite dead-letter queue for rows that failed to upload.
+const RETRY_QUEUE_FILE: &str = "retry_queue.sqlite";
+
+/// Channel heartbeats are published to when `[REDIS]` doesn't set `CHANNEL`.
+const DEFAULT_REDIS_CHANNEL: &str = "aoi_uploader_status";
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -20,14 +42,70 @@ async fn main() -> Result<()> {
     }
 
     env_logger::init();
+
+    // `check <dir>` validates a batch of XML files against the SQL-free
+    // parser and exits, instead of starting the uploader.
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("check") {
+        let Some(dir) = args.get(2) else {
+            eprintln!("Usage: {} check <directory>", args[0]);
+            std::process::exit(2);
+        };
+
+        let line = Config::load().map(|c| c.AOI_line).unwrap_or_default();
+        let all_ok = check::check(Path::new(dir), &line)?;
+        std::process::exit(if all_ok { 0 } else { 1 });
+    }
+
+    // `restore` drains the offline spool into SQL, e.g. after an outage.
+    if args.get(1).map(String::as_str) == Some("restore") {
+        let config = Config::load()?;
+        let mut client = create_connection(&config).await?;
+
+        let resume_after = get_last_spool_seq();
+        let applied = spool::restore(Path::new(SPOOL_FILE), resume_after, |panel, seq| {
+            let client = &mut client;
+            async move {
+                upload_chunk(client, std::slice::from_ref(&panel)).await?;
+                // Persisted as each row lands, not just once `restore` returns,
+                // so a later failure doesn't replay rows already inserted here.
+                put_last_spool_seq(seq);
+                Ok(())
+            }
+        })
+        .await?;
+
+        if let Some(seq) = applied {
+            put_last_spool_seq(seq);
+        }
+
+        std::process::exit(0);
+    }
+
+    // `--setup` (or no config file at all on a bare invocation) walks a
+    // technician through producing a working `config.ini` instead of
+    // making them hand-edit one after a cryptic `Config::load` error.
+    let setup_requested = args.get(1).map(String::as_str) == Some("--setup");
+    if setup_requested || (args.len() == 1 && !Path::new("config.ini").exists()) {
+        run_setup_wizard().await?;
+        std::process::exit(0);
+    }
+
     info!("Starting uploader");
 
     let (tx, rx) = mpsc::sync_channel(1);
     let sql_tx = tx.clone();
 
+    let shutdown = Arc::new(Shutdown::new());
+    if let Err(e) = install_shutdown_handler(shutdown.clone()) {
+        error!("Failed to install shutdown handler: {e}, Ctrl-C/SIGTERM won't finish in-flight work");
+    }
+
     // SQL uploader thread
+    let uploader_shutdown = shutdown.clone();
     tokio::spawn(async move {
-        
+        let shutdown = uploader_shutdown;
+
         let config = Config::load();
         if config.is_err() {
             error!("Failed to load configuration! Terminating.");
@@ -38,20 +116,47 @@ async fn main() -> Result<()> {
         let log_dir = PathBuf::from(config.AOI_dir.clone());
         let delta_t = Duration::from_secs(config.AOI_deltat);
 
-        let mut client = 
-        loop {
-            if let Ok(client) =  create_connection(&config).await {
-                break client;
+        let mut change_rx = watch_dir(log_dir.clone());
+
+        let retry_queue = match retry::RetryQueue::open(RETRY_QUEUE_FILE).await {
+            Ok(q) => q,
+            Err(e) => {
+                error!("Failed to open retry queue: {e}");
+                sql_tx.send(Message::FatalError).unwrap();
+                return;
             }
+        };
 
-            sql_tx.send(Message::SetIcon(IconCollor::Red)).unwrap();
-            error!("Failed to connect to the SQL server, retrying in 60s.");
-            sleep(Duration::from_secs(60)).await;
-        }
-        ;        
+        let status_publisher = if config.redis_url.is_empty() {
+            None
+        } else {
+            let channel = if config.redis_channel.is_empty() {
+                DEFAULT_REDIS_CHANNEL
+            } else {
+                &config.redis_channel
+            };
+
+            match status::StatusPublisher::connect(&config.redis_url, channel).await {
+                Ok(p) => Some(p),
+                Err(e) => {
+                    error!("Failed to connect to Redis: {e}, status reporting disabled");
+                    None
+                }
+            }
+        };
 
+        let Some(mut client) = connect_with_retry(&config, &sql_tx, &shutdown).await else {
+            info!("Shutdown requested while waiting for the initial connection, exiting");
+            let _ = sql_tx.send(Message::Quit);
+            return;
+        };
 
         sql_tx.send(Message::SetIcon(IconCollor::Green)).unwrap();
+        report_status(&status_publisher, &config.AOI_line, IconCollor::Green, None, 0, None).await;
+        let mut had_connection_error = false;
+        // Directories the watcher reported changes in since the last iteration;
+        // `None` forces the next iteration to fall back to a full corpus scan.
+        let mut changed_dirs: Option<Vec<PathBuf>> = None;
 
         loop {
 
@@ -63,32 +168,67 @@ async fn main() -> Result<()> {
                     }
                     Err(_) => {
                         warn!("Connection to DB lost, reconnecting!");
-                        client = 
-                        loop {
-                            if let Ok(client) =  create_connection(&config).await {
-                                break client;
-                            }
-
-                            sql_tx.send(Message::SetIcon(IconCollor::Red)).unwrap();
-                            error!("Failed to connect to the SQL server, retrying in 60s.");
-                            sleep(Duration::from_secs(60)).await;
-                        }
-                        ;  
+                        toast(
+                            &sql_tx,
+                            &config,
+                            NotifyLevel::Warning,
+                            "Connection to DB lost, reconnecting...",
+                        );
+                        had_connection_error = true;
+                        report_status(
+                            &status_publisher,
+                            &config.AOI_line,
+                            IconCollor::Red,
+                            None,
+                            0,
+                            Some("Connection to DB lost".to_owned()),
+                        )
+                        .await;
+
+                        let Some(new_client) = connect_with_retry(&config, &sql_tx, &shutdown).await else {
+                            info!("Shutdown requested while reconnecting, exiting uploader loop");
+                            let _ = sql_tx.send(Message::Quit);
+                            return;
+                        };
+                        client = new_client;
                     }
                 }
             }
 
+            if had_connection_error {
+                info!("Connection to DB restored");
+                toast(&sql_tx, &config, NotifyLevel::Info, "Connection to DB restored");
+                report_status(&status_publisher, &config.AOI_line, IconCollor::Green, None, 0, None).await;
+                had_connection_error = false;
+            }
 
             debug!("AOI auto update started");
             let start_time = chrono::Local::now();
-            
+
+            // 0.5 - drain due rows from the retry queue before processing new logs
+            if let Err(e) = retry_queue
+                .drain(|row| {
+                    let client = &mut client;
+                    async move { upload_pending_row(client, &row).await }
+                })
+                .await
+            {
+                error!("Failed to drain retry queue: {e}");
+            }
 
             // 1 - get date_time of the last update
             if let Ok(last_date) = get_last_date() {
-                let last_date = last_date - delta_t; 
-
-                // 2 - get possible directories
-                let target_dirs = get_subdirs_for_aoi(&log_dir, &last_date);
+                let last_date = last_date - delta_t;
+
+                // 2 - get possible directories: if the watcher woke us up with
+                // specific changed directories, only those get re-stat'ed (still
+                // filtered by last_date below, same as the full scan); otherwise
+                // (startup, or the 300s fallback) walk every dated subdirectory
+                // so nothing missed while the watcher was down gets skipped.
+                let target_dirs = match changed_dirs.take() {
+                    Some(dirs) if !dirs.is_empty() => dirs,
+                    _ => get_subdirs_for_aoi(&log_dir, &last_date),
+                };
 
                 // 3 - get logs
                 if let Ok(logs) = get_logs(target_dirs, last_date) {
@@ -96,72 +236,88 @@ async fn main() -> Result<()> {
 
                     let mut processed_logs = Vec::new();
                     for log in logs {
-                        if let Ok(plog) = panel::parse_xml(&log, &config.AOI_line) {
-                            processed_logs.push(plog);
-                        } else {
-                            error!("Failed to process log: {:?}", log);
+                        match panel::parse_xml(
+                            &log,
+                            &config.AOI_line,
+                            &panel::StationProfile::REPAIR,
+                            &panel::StationProfile::AOI_AXI,
+                        ) {
+                            Ok(plog) => processed_logs.push(plog),
+                            Err(diagnostics) => {
+                                error!("Failed to process log: {:?}", log);
+                                for d in diagnostics {
+                                    error!("  {d}");
+                                }
+                            }
                         }
                     }
 
                     let mut all_ok = true;
+                    let mut rows_uploaded = 0usize;
+                    let mut last_error = None;
                     // uploading in chunks
                     for chunk in processed_logs.chunks(config.AOI_chunks) {
-                        // 5 - craft the SQL query
-
-                        let mut qtext = String::from(
-                            "INSERT INTO [dbo].[SMT_AOI_RESULTS] 
-                            ([Serial_NMBR], [Board_NMBR], [Program], [Station], [Operator], [Result], [Date_Time], [Failed], [Pseudo_error])
-                            VALUES",
-                        );
-
-                        for panel in chunk {
-                            for board in &panel.Boards {
-                                let fails = board.Failed.join(", ");
-                                let pseudo = board.Pseudo.join(", ");
-                    
-                                qtext += &format!(
-                                    "('{}', '{}', '{}', '{}', '{}', '{}', '{}', '{}', '{}'),",
-                                    board.Serial_NMBR,
-                                    board.Board_NMBR,
-                                    panel.Program,
-                                    panel.Station,
-                                    panel.Operator,
-                                    board.Result,
-                                    if panel.Operator.is_empty() {
-                                        panel.Inspection_DT
-                                    } else {
-                                        panel.Repair_DT
-                                    },
-                                    fails,
-                                    pseudo
-                                );
-                            }
-                        }
-                        qtext.pop(); // removes last ','
-
-                        // 6 - execute query
-                        debug!("Upload: {}", qtext);
-                        let query = Query::new(qtext);
-                        let result = query.execute(&mut client).await;
-
-                        debug!("Result: {:?}", result);
-
-                        if let Err(e) = result {
+                        if let Err(e) = upload_chunk(&mut client, chunk).await {
                             all_ok = false;
                             error!("Upload failed: {e}");
+                            toast(
+                                &sql_tx,
+                                &config,
+                                NotifyLevel::Error,
+                                &format!("Upload failed: {e}"),
+                            );
+                            last_error = Some(e.source.to_string());
+
+                            // upload_chunk may have already committed the chunk's
+                            // earlier rows via an earlier, successful sub-statement
+                            // before this one failed - only the boards after
+                            // `e.committed` are actually missing from the DB, so
+                            // only those get queued for retry.
+                            let entries = flatten_boards(chunk);
+                            let rows = retry::PendingRow::from_entries(&entries[e.committed..]);
+
+                            // The retry queue is the one durability mechanism for the
+                            // live path - it auto-drains and re-inserts these rows next
+                            // cycle. Only fall back to the spool (which a later `restore`
+                            // run would separately re-insert) if the queue itself can't
+                            // be written to, so a row is never durable in both places.
+                            if let Err(qe) = retry_queue.enqueue(&rows, &e.source.to_string()).await {
+                                error!("Failed to queue rows for retry: {qe}");
+
+                                // Same partial-commit boundary as the retry-queue
+                                // path above: only the not-yet-committed boards are
+                                // missing from the DB, so only those get spooled.
+                                if let Err(e) =
+                                    spool::dump(Path::new(SPOOL_FILE), remaining_panels(chunk, e.committed))
+                                {
+                                    error!("Failed to spool chunk for later retry: {e}");
+                                }
+                            }
                         } else {
                             debug!("Upload succesfull!");
+                            rows_uploaded += chunk.iter().map(|p| p.Boards.len()).sum::<usize>();
                         }
                     }
 
-                    // 7 - update last_date or report the error
+                    // 7 - advance last_date regardless of per-chunk failures: failed
+                    // rows are now durably queued for retry instead of forcing the
+                    // whole window to be reprocessed next cycle.
                     if all_ok {
                         sql_tx.send(Message::SetIcon(IconCollor::Green)).unwrap();
-                        put_last_date(start_time);
                     } else {
-                        sql_tx.send(Message::SetIcon(IconCollor::Red)).unwrap();
-                        error!("Upload failed - not setting new last_date");
+                        sql_tx.send(Message::SetIcon(IconCollor::Yellow)).unwrap();
+                        error!("Some rows failed to upload and were queued for retry");
                     }
+                    put_last_date(start_time);
+                    report_status(
+                        &status_publisher,
+                        &config.AOI_line,
+                        if all_ok { IconCollor::Green } else { IconCollor::Yellow },
+                        Some(start_time),
+                        rows_uploaded,
+                        last_error,
+                    )
+                    .await;
                 } else {
                     error!("Failed to gather logs!");
                 }
@@ -169,9 +325,30 @@ async fn main() -> Result<()> {
                 error!("Failed to read last_date!");
             }
 
-            // wait 5 minutes and repeat
-            sleep(Duration::from_secs(300)).await;
+            // Repeat as soon as the watcher reports a (debounced) filesystem
+            // change, or after 5 minutes regardless - the timestamp scan is
+            // the fallback that catches up on anything missed while the app
+            // was offline or the watcher failed to start. A watcher wakeup
+            // also narrows the next iteration's directory scan to just the
+            // changed directories (see `changed_dirs` above), instead of
+            // re-stat'ing every dated subdirectory.
+            tokio::select! {
+                _ = sleep(Duration::from_secs(300)) => {
+                    debug!("Fallback scan interval elapsed");
+                    changed_dirs = None;
+                }
+                dirs = debounced_change(&mut change_rx) => {
+                    debug!("Filesystem change detected in {} dir(s), rescanning early", dirs.len());
+                    changed_dirs = Some(dirs);
+                }
+                _ = shutdown.wait() => {
+                    info!("Shutdown requested, uploader loop exiting after its last batch");
+                    break;
+                }
+            }
         }
+
+        let _ = sql_tx.send(Message::Quit);
     });
 
     let (mut tray, _) = init_tray(tx.clone());
@@ -209,6 +386,24 @@ async fn main() -> Result<()> {
                     warn!("Failed to change icon to: {target_col}");
                 }
             }
+            Ok(Message::Notify { title, body, level }) => {
+                debug!("Notification requested [{:?}]: {title}: {body}", level);
+
+                let urgency = match level {
+                    NotifyLevel::Info => notify_rust::Urgency::Low,
+                    NotifyLevel::Warning => notify_rust::Urgency::Normal,
+                    NotifyLevel::Error => notify_rust::Urgency::Critical,
+                };
+
+                if let Err(e) = Notification::new()
+                    .summary(&title)
+                    .body(&body)
+                    .urgency(urgency)
+                    .show()
+                {
+                    warn!("Failed to show desktop notification: {e}");
+                }
+            }
             _ => {}
         }
     }
@@ -227,6 +422,12 @@ struct Config {
     AOI_line: String,
     AOI_chunks: usize,
     AOI_deltat: u64,
+
+    notify_enabled: bool,
+    notify_min_level: NotifyLevel,
+
+    redis_url: String,
+    redis_channel: String,
 }
 
 impl Config {
@@ -290,6 +491,32 @@ impl Config {
                 return Err(anyhow::Error::msg("ER: Could not find [AOI] field!"));
             }
 
+            // [NOTIFY] is optional: notifications stay off unless an operator
+            // opts in, so existing installs keep behaving as before.
+            if let Some(notify) = config.section(Some("NOTIFY")) {
+                c.notify_enabled = notify
+                    .get("ENABLED")
+                    .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+                    .unwrap_or(false);
+
+                c.notify_min_level = match notify.get("MIN_LEVEL") {
+                    Some(l) if l.eq_ignore_ascii_case("WARNING") => NotifyLevel::Warning,
+                    Some(l) if l.eq_ignore_ascii_case("ERROR") => NotifyLevel::Error,
+                    _ => NotifyLevel::Info,
+                };
+            }
+
+            // [REDIS] is optional: without it no `StatusPublisher` is ever
+            // constructed, so installs without Redis behave as today.
+            if let Some(redis) = config.section(Some("REDIS")) {
+                if let Some(url) = redis.get("URL") {
+                    c.redis_url = url.to_owned();
+                }
+                if let Some(channel) = redis.get("CHANNEL") {
+                    c.redis_channel = channel.to_owned();
+                }
+            }
+
         } else {
             return Err(anyhow::Error::msg(
                 "ER: Could not read configuration file! [.\\config.ini]",
@@ -300,6 +527,100 @@ impl Config {
     }
 }
 
+/// Walks a technician through producing a working `config.ini`: prompts for
+/// every mandatory `[JVSERVER]`/`[AOI]` value, validates the SQL server is
+/// reachable via [`create_connection`] and that the AOI directory exists,
+/// then writes the file. Run via `--setup`, or automatically on a bare
+/// invocation with no `config.ini` present yet.
+async fn run_setup_wizard() -> Result<()> {
+    println!("AOI Uploader setup wizard");
+    println!("-------------------------");
+
+    let mut config = Config {
+        server: prompt("SQL server (host or host,port)")?,
+        database: prompt("Database name")?,
+        username: prompt("SQL username")?,
+        password: prompt("SQL password")?,
+        AOI_dir: prompt("AOI log directory")?,
+        AOI_line: prompt("AOI line name")?,
+        AOI_chunks: prompt_with_default("Upload chunk size", "10")?
+            .parse()
+            .unwrap_or(10),
+        AOI_deltat: prompt_with_default("Catch-up delta, in seconds", "0")?
+            .parse()
+            .unwrap_or(0),
+        ..Config::default()
+    };
+
+    if !Path::new(&config.AOI_dir).is_dir() {
+        bail!("AOI log directory {:?} does not exist", config.AOI_dir);
+    }
+
+    println!("Validating SQL connection...");
+    create_connection(&config).await?;
+    println!("Connection OK.");
+
+    // Normalize away accidental cross-run config, so `config.ini` only
+    // reflects what was just entered.
+    config.notify_enabled = false;
+    config.redis_url.clear();
+
+    write_config_ini(&config)?;
+    println!("Wrote config.ini");
+
+    Ok(())
+}
+
+fn prompt(label: &str) -> Result<String> {
+    loop {
+        print!("{label}: ");
+        std::io::Write::flush(&mut std::io::stdout())?;
+
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line)?;
+        let line = line.trim();
+        if !line.is_empty() {
+            return Ok(line.to_owned());
+        }
+
+        println!("  this field is required");
+    }
+}
+
+fn prompt_with_default(label: &str, default: &str) -> Result<String> {
+    print!("{label} [{default}]: ");
+    std::io::Write::flush(&mut std::io::stdout())?;
+
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line)?;
+    let line = line.trim();
+
+    Ok(if line.is_empty() {
+        default.to_owned()
+    } else {
+        line.to_owned()
+    })
+}
+
+fn write_config_ini(config: &Config) -> Result<()> {
+    let mut ini = ini::Ini::new();
+
+    ini.with_section(Some("JVSERVER"))
+        .set("SERVER", &config.server)
+        .set("DATABASE", &config.database)
+        .set("USERNAME", &config.username)
+        .set("PASSWORD", &config.password);
+
+    ini.with_section(Some("AOI"))
+        .set("DIR", &config.AOI_dir)
+        .set("LINE", &config.AOI_line)
+        .set("CHUNKS", config.AOI_chunks.to_string())
+        .set("DELTA_T", config.AOI_deltat.to_string());
+
+    ini.write_to_file("config.ini")?;
+    Ok(())
+}
+
 async fn connect(
     tib_config: tiberius::Config,
 ) -> anyhow::Result<tiberius::Client<tokio_util::compat::Compat<TcpStream>>> {
@@ -310,6 +631,194 @@ async fn connect(
     Ok(client)
 }
 
+/// Number of `@P`-placeholders bound per row by [`upload_chunk`] and
+/// [`upload_pending_row`].
+const ROW_PARAMS: usize = 9;
+
+/// SQL Server (and tiberius) cap a single statement at 2100 parameters.
+/// `AOI_chunks` is operator-configurable and each panel can expand to
+/// several boards, so one parameterized `INSERT` per full chunk can exceed
+/// that cap; split into statements of at most this many rows instead.
+const MAX_ROWS_PER_STATEMENT: usize = 2100 / ROW_PARAMS;
+
+/// Error from [`upload_chunk`] that also reports how many of the chunk's
+/// boards (in flattening order) were already committed in an earlier
+/// sub-statement before a later one failed, so a caller re-queuing the
+/// chunk for retry can skip the rows that are already in the database.
+pub struct UploadChunkError {
+    pub committed: usize,
+    pub source: anyhow::Error,
+}
+
+impl std::fmt::Debug for UploadChunkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.source.fmt(f)
+    }
+}
+
+impl std::fmt::Display for UploadChunkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.source.fmt(f)
+    }
+}
+
+impl std::error::Error for UploadChunkError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source.source()
+    }
+}
+
+/// Flattens `chunk`'s panels into `(Panel, Board)` pairs in the same order
+/// [`upload_chunk`] uploads them, so a caller can map a partial-failure
+/// `committed` count back onto the boards still left to retry.
+pub fn flatten_boards(chunk: &[panel::Panel]) -> Vec<(&panel::Panel, &panel::Board)> {
+    chunk
+        .iter()
+        .flat_map(|panel| panel.Boards.iter().map(move |board| (panel, board)))
+        .collect()
+}
+
+/// Rebuilds `chunk` as owned panels holding only the boards at or after
+/// `committed` in [`flatten_boards`] order, so a caller durably persisting
+/// what's left of a partially-committed chunk (e.g. to the spool) doesn't
+/// also re-persist boards an earlier sub-statement already got into the DB.
+/// A panel whose boards straddle `committed` keeps only its remaining ones.
+pub fn remaining_panels(chunk: &[panel::Panel], committed: usize) -> Vec<panel::Panel> {
+    let entries = flatten_boards(chunk);
+    let mut remaining = Vec::new();
+    let mut i = committed;
+
+    while i < entries.len() {
+        let source = entries[i].0;
+        let boards: Vec<panel::Board> = entries[i..]
+            .iter()
+            .take_while(|(panel, _)| std::ptr::eq(*panel, source))
+            .map(|(_, board)| board.clone())
+            .collect();
+
+        i += boards.len();
+        remaining.push(panel::Panel {
+            Boards: boards,
+            ..source.clone()
+        });
+    }
+
+    remaining
+}
+
+/// Inserts one chunk of parsed panels, shared by the regular uploader loop
+/// and the offline-spool `restore` path.
+async fn upload_chunk(
+    client: &mut Client<tokio_util::compat::Compat<TcpStream>>,
+    chunk: &[panel::Panel],
+) -> Result<(), UploadChunkError> {
+    let entries = flatten_boards(chunk);
+    let mut committed = 0;
+
+    for rows in entries.chunks(MAX_ROWS_PER_STATEMENT) {
+        upload_rows(client, rows)
+            .await
+            .map_err(|source| UploadChunkError { committed, source })?;
+        committed += rows.len();
+    }
+
+    Ok(())
+}
+
+async fn upload_rows(
+    client: &mut Client<tokio_util::compat::Compat<TcpStream>>,
+    rows: &[(&panel::Panel, &panel::Board)],
+) -> Result<()> {
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    let mut qtext = String::from(
+        "INSERT INTO [dbo].[SMT_AOI_RESULTS]
+        ([Serial_NMBR], [Board_NMBR], [Program], [Station], [Operator], [Result], [Date_Time], [Failed], [Pseudo_error])
+        VALUES",
+    );
+
+    for i in 0..rows.len() {
+        let base = i * ROW_PARAMS;
+        qtext.push('(');
+        for col in 0..ROW_PARAMS {
+            if col > 0 {
+                qtext.push(',');
+            }
+            qtext += &format!("@P{}", base + col + 1);
+        }
+        qtext.push_str("),");
+    }
+    qtext.pop(); // removes last ','
+
+    // Parameterized via tiberius `Query::bind` instead of interpolating
+    // values into the SQL text, so a quote in e.g. Operator can't break
+    // out of the statement.
+    let mut query = Query::new(qtext);
+    for (panel, board) in rows {
+        query.bind(board.Serial_NMBR.clone());
+        query.bind(board.Board_NMBR as i32);
+        query.bind(panel.Program.clone());
+        query.bind(panel.Station.clone());
+        query.bind(non_empty(&panel.Operator));
+        query.bind(board.Result.clone());
+        query.bind(if panel.Operator.is_empty() {
+            panel.Inspection_DT
+        } else {
+            panel.Repair_DT
+        });
+        // Failed/Pseudo_error nullability beyond what the baseline schema
+        // comment documents isn't confirmed; keep them as the baseline did,
+        // bound as plain (possibly empty) strings rather than NULL.
+        query.bind(board.Failed.join(", "));
+        query.bind(board.Pseudo.join(", "));
+    }
+
+    debug!("Uploading {} row(s)", rows.len());
+    let result = query.execute(client).await;
+    debug!("Result: {:?}", result);
+
+    result?;
+    Ok(())
+}
+
+/// `Operator` is allowed NULL in SQL; bind an empty string as NULL rather
+/// than as `''`.
+fn non_empty(s: &str) -> Option<String> {
+    if s.is_empty() {
+        None
+    } else {
+        Some(s.to_owned())
+    }
+}
+
+/// Inserts a single row out of the retry queue.
+async fn upload_pending_row(
+    client: &mut Client<tokio_util::compat::Compat<TcpStream>>,
+    row: &retry::PendingRow,
+) -> Result<()> {
+    let qtext = "INSERT INTO [dbo].[SMT_AOI_RESULTS]
+        ([Serial_NMBR], [Board_NMBR], [Program], [Station], [Operator], [Result], [Date_Time], [Failed], [Pseudo_error])
+        VALUES (@P1, @P2, @P3, @P4, @P5, @P6, @P7, @P8, @P9)";
+
+    let mut query = Query::new(qtext);
+    query.bind(row.serial_nmbr.clone());
+    query.bind(row.board_nmbr as i32);
+    query.bind(row.program.clone());
+    query.bind(row.station.clone());
+    query.bind(non_empty(&row.operator));
+    query.bind(row.result.clone());
+    query.bind(row.date_time);
+    query.bind(row.failed.clone());
+    query.bind(row.pseudo.clone());
+
+    debug!("Retry upload for {}", row.serial_nmbr);
+    query.execute(client).await?;
+
+    Ok(())
+}
+
 async fn create_connection(config: &Config) -> Result<Client<tokio_util::compat::Compat<TcpStream>>> {
         // Tiberius configuartion:
 
@@ -343,6 +852,159 @@ async fn create_connection(config: &Config) -> Result<Client<tokio_util::compat:
         Ok(client)
 }
 
+/// Retries [`create_connection`] every 60s until it succeeds, reporting the
+/// red icon on each failed attempt. Bails out early with `None` if
+/// `shutdown` fires while waiting, so a termination signal received while
+/// the DB is down doesn't hang the uploader until it comes back.
+async fn connect_with_retry(
+    config: &Config,
+    sql_tx: &SyncSender<Message>,
+    shutdown: &Shutdown,
+) -> Option<Client<tokio_util::compat::Compat<TcpStream>>> {
+    loop {
+        if let Ok(client) = create_connection(config).await {
+            return Some(client);
+        }
+
+        sql_tx.send(Message::SetIcon(IconCollor::Red)).unwrap();
+        error!("Failed to connect to the SQL server, retrying in 60s.");
+
+        tokio::select! {
+            _ = sleep(Duration::from_secs(60)) => {}
+            _ = shutdown.wait() => {
+                return None;
+            }
+        }
+    }
+}
+
+/// Watches `dir` recursively for created/modified `*.xml` files and
+/// returns a channel that receives one message per such change. [`debounced_change`]
+/// turns these into the set of changed directories, which the uploader loop
+/// scans instead of every dated subdirectory - both making the scan run
+/// sooner than the 300s fallback, and keeping it cheap when it does.
+/// Runs the watcher on its own thread since `notify`'s callback-based API is
+/// not async; if the watcher can't be started, the returned channel is
+/// simply never sent to, and the uploader loop falls back to its 5-minute
+/// full-corpus scan.
+fn watch_dir(dir: PathBuf) -> mpsc_tokio::UnboundedReceiver<PathBuf> {
+    let (tx, rx) = mpsc_tokio::unbounded_channel::<PathBuf>();
+
+    std::thread::spawn(move || {
+        let (std_tx, std_rx) = std::sync::mpsc::channel();
+
+        let mut watcher = match notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            let Ok(event) = res else { return };
+            if !matches!(event.kind, notify::EventKind::Create(_) | notify::EventKind::Modify(_)) {
+                return;
+            }
+            for path in event.paths {
+                if path.extension().is_some_and(|e| e == "xml" || e == "XML") {
+                    let _ = std_tx.send(path);
+                }
+            }
+        }) {
+            Ok(w) => w,
+            Err(e) => {
+                error!("Failed to create filesystem watcher: {e}, falling back to scan-only mode");
+                return;
+            }
+        };
+
+        if let Err(e) = watcher.watch(&dir, RecursiveMode::Recursive) {
+            error!("Failed to watch {:?}: {e}, falling back to scan-only mode", dir);
+            return;
+        }
+
+        info!("Watching {:?} for XML changes", dir);
+        for path in std_rx {
+            if tx.send(path).is_err() {
+                break;
+            }
+        }
+    });
+
+    rx
+}
+
+/// Waits for the first filesystem change, then coalesces any further
+/// changes arriving within a short burst window into the same wakeup, so a
+/// directory full of near-simultaneous writes triggers one rescan instead
+/// of several. Returns the distinct parent directories of every changed
+/// path seen in the burst, so the caller can re-stat just those instead of
+/// the whole corpus.
+async fn debounced_change(rx: &mut mpsc_tokio::UnboundedReceiver<PathBuf>) -> Vec<PathBuf> {
+    let Some(first) = rx.recv().await else {
+        // Watcher never started or died; let the fallback sleep drive the loop.
+        std::future::pending::<()>().await;
+        return Vec::new();
+    };
+
+    let mut dirs = std::collections::HashSet::new();
+    if let Some(dir) = first.parent() {
+        dirs.insert(dir.to_path_buf());
+    }
+
+    while let Ok(Some(path)) = tokio::time::timeout(Duration::from_millis(750), rx.recv()).await {
+        if let Some(dir) = path.parent() {
+            dirs.insert(dir.to_path_buf());
+        }
+    }
+
+    dirs.into_iter().collect()
+}
+
+/// Coordinates a graceful shutdown: [`install_shutdown_handler`] calls
+/// `trigger` on Ctrl-C/SIGTERM, and the uploader loop's `wait` call wakes up
+/// at its next iteration boundary - after the current chunk and
+/// `put_last_date` have already run - instead of the process dying mid-batch.
+struct Shutdown {
+    requested: std::sync::atomic::AtomicBool,
+    notify: tokio::sync::Notify,
+}
+
+impl Shutdown {
+    fn new() -> Self {
+        Shutdown {
+            requested: std::sync::atomic::AtomicBool::new(false),
+            notify: tokio::sync::Notify::new(),
+        }
+    }
+
+    fn trigger(&self) {
+        self.requested
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+        self.notify.notify_waiters();
+    }
+
+    /// Resolves immediately if shutdown was already requested; otherwise
+    /// waits for `trigger`. The `Notified` future is created before the
+    /// flag check so a `trigger` racing with this call is never missed.
+    async fn wait(&self) {
+        let notified = self.notify.notified();
+        if self.requested.load(std::sync::atomic::Ordering::Relaxed) {
+            return;
+        }
+        notified.await;
+    }
+}
+
+/// Spawns a thread blocking on `signal_hook`'s signal iterator and triggers
+/// `shutdown` on Ctrl-C/SIGTERM, mirroring how `watch_dir` bridges its own
+/// blocking, callback-based API onto the async runtime.
+fn install_shutdown_handler(shutdown: Arc<Shutdown>) -> Result<()> {
+    let mut signals = Signals::new([SIGINT, SIGTERM])?;
+
+    std::thread::spawn(move || {
+        if signals.forever().next().is_some() {
+            info!("Shutdown signal received, finishing in-flight work...");
+            shutdown.trigger();
+        }
+    });
+
+    Ok(())
+}
+
 fn get_logs(target_dirs: Vec<PathBuf>, last_date: DateTime<Local>) -> Result<Vec<PathBuf>> {
     let mut ret = Vec::new();
 
@@ -408,6 +1070,18 @@ fn put_last_date(end_time: DateTime<Local>) {
     let _ = fs::write("last_date.txt", output_string);
 }
 
+/// Seq of the last spool record a `restore` run successfully applied, so a
+/// later run can resume the drain instead of re-inserting old rows.
+fn get_last_spool_seq() -> Option<u64> {
+    fs::read_to_string("spool_applied.txt")
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+}
+
+fn put_last_spool_seq(seq: u64) {
+    let _ = fs::write("spool_applied.txt", seq.to_string());
+}
+
 fn get_subdirs_for_aoi(log_dir: &Path, start: &chrono::DateTime<chrono::Local>) -> Vec<PathBuf> {
     let mut ret = Vec::new();
 
@@ -443,10 +1117,62 @@ pub enum IconCollor {
     Grey,
     Purple,
 }
+
+/// Severity of a [`Message::Notify`] toast, compared against
+/// `Config::notify_min_level` to decide whether it's actually shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NotifyLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Default for NotifyLevel {
+    fn default() -> Self {
+        NotifyLevel::Info
+    }
+}
+
 pub enum Message {
     Quit,
     FatalError,
     SetIcon(IconCollor),
+    Notify {
+        title: String,
+        body: String,
+        level: NotifyLevel,
+    },
+}
+
+/// Publishes a heartbeat through `publisher`, if one is configured, so a
+/// central dashboard can render this line's status alongside every other
+/// uploader instance. A no-op when `[REDIS]` isn't configured.
+async fn report_status(
+    publisher: &Option<status::StatusPublisher>,
+    line: &str,
+    icon: IconCollor,
+    last_date: Option<DateTime<Local>>,
+    rows_uploaded: usize,
+    last_error: Option<String>,
+) {
+    let Some(publisher) = publisher else { return };
+    let heartbeat = status::Heartbeat::new(line, &icon, last_date, rows_uploaded, last_error, Local::now());
+    publisher.publish(&heartbeat).await;
+}
+
+/// Sends a desktop toast through the tray loop if notifications are enabled
+/// and `level` meets the configured minimum severity, so the shop floor
+/// gets alerted to state changes without having to watch the tray icon.
+fn toast(tx: &SyncSender<Message>, config: &Config, level: NotifyLevel, body: &str) {
+    if !config.notify_enabled || level < config.notify_min_level {
+        return;
+    }
+
+    let _ = tx.send(Message::Notify {
+        title: "AOI Uploader".to_owned(),
+        body: body.to_owned(),
+        level,
+    });
 }
 
 pub fn init_tray(tx: SyncSender<Message>) -> (TrayItem, Vec<u32>) {
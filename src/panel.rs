@@ -11,14 +11,40 @@ SQL fields:
 */
 
 use std::path::PathBuf;
-use anyhow::{bail, Result};
+use anyhow::Result;
 use chrono::{Datelike, NaiveDateTime};
 use log::{debug, error, info};
+use roxmltree::Node;
+use serde::{Deserialize, Serialize};
+
+/// A single problem found while interpreting an XML file, located by the
+/// row/column of the offending node so an operator can jump straight to it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub path: PathBuf,
+    pub message: String,
+    pub row: u32,
+    pub col: u32,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}",
+            self.path.display(),
+            self.row,
+            self.col,
+            self.message
+        )
+    }
+}
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Panel {
      pub Program: String,
     pub Station: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub Operator: String,
     pub Repair_DT: NaiveDateTime,
     pub Inspection_DT: NaiveDateTime,
@@ -26,31 +52,164 @@ pub struct Panel {
     pub Boards: Vec<Board>,
 }
 
-#[derive(Debug, Default, Clone)]
+impl Panel {
+    /// Serializes this `Panel` to a JSON string, e.g. for spooling to disk
+    /// when the SQL target is unreachable, or for diffing in tests.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Board {
     pub Serial_NMBR: String,
     pub Board_NMBR: usize,
     pub Result: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub Failed: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub Pseudo: Vec<String>,
 }
 
-pub fn parse_xml(path: &PathBuf, line: &str) -> Result<Panel> {
+/// How a window's `PCBNumber` maps to a board index, since station
+/// dialects disagree on whether `PCBNumber` is already 0-based.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoardIndexBase {
+    /// `PCBNumber` is used directly as the board index.
+    ZeroBased,
+    /// `PCBNumber` is 1-based; subtract one to get the board index.
+    OneBased,
+}
+
+fn board_index(base: BoardIndexBase, pcb_number: usize) -> Option<usize> {
+    match base {
+        BoardIndexBase::ZeroBased => Some(pcb_number),
+        BoardIndexBase::OneBased => pcb_number.checked_sub(1),
+    }
+}
+
+/// How `WinID` should be trimmed before being recorded against a board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WinIdTrim {
+    /// Keep `WinID` as-is.
+    None,
+    /// Drop everything from the last `-` onward.
+    TrimAfterLastDash,
+}
+
+fn trim_win_id(win_id: &mut String, strategy: WinIdTrim) {
+    if strategy == WinIdTrim::TrimAfterLastDash {
+        if let Some(c) = win_id.rfind('-') {
+            win_id.truncate(c);
+        }
+    }
+}
+
+/// Per-station parsing rules. `parse_xml` still detects whether a file is
+/// a repair-station or an AOI/AXI-station log from its `<GlobalInformation>`
+/// content, but every other station-specific convention - board indexing,
+/// `WinID` trimming, the pseudo/pass markers and the station-name suffix -
+/// is read from the matching profile, so new inspection equipment with
+/// different XML conventions can be onboarded without editing the parser.
+#[derive(Debug, Clone)]
+pub struct StationProfile {
+    pub board_index_base: BoardIndexBase,
+    pub win_id_trim: WinIdTrim,
+    /// Window `Result` value that marks a pseudo (non-real) failure.
+    /// Empty if this profile has no pseudo-failure concept.
+    pub pseudo_marker: &'static str,
+    /// Window `Result` value that marks a passing (non-failure) window.
+    /// Empty if this profile has no pass marker at window granularity.
+    pub pass_marker: &'static str,
+    /// Appended to `line` to build `Panel::Station`.
+    pub station_suffix: &'static str,
+}
+
+impl StationProfile {
+    /// The repair ("HARAN") station: `PCBNumber` indexes boards directly,
+    /// and `"Pszeudohiba"` marks a pseudo (non-real) failure.
+    pub const REPAIR: StationProfile = StationProfile {
+        board_index_base: BoardIndexBase::ZeroBased,
+        win_id_trim: WinIdTrim::TrimAfterLastDash,
+        pseudo_marker: "Pszeudohiba",
+        pass_marker: "",
+        station_suffix: "_HARAN",
+    };
+
+    /// The AOI/AXI station: `PCBNumber` is 1-based, and `"0"` marks a
+    /// passing window.
+    pub const AOI_AXI: StationProfile = StationProfile {
+        board_index_base: BoardIndexBase::OneBased,
+        win_id_trim: WinIdTrim::TrimAfterLastDash,
+        pseudo_marker: "",
+        pass_marker: "0",
+        station_suffix: "_AOI_AXI",
+    };
+}
+
+/// Parses a single AOI/repair station XML log into a [`Panel`].
+///
+/// Recoverable problems (a bad board, a missing sub-field, an unparseable
+/// `PCBNumber`, ...) are collected as [`Diagnostic`]s and parsing continues
+/// past them so a single run surfaces every problem in the file instead of
+/// just the first one. The file only fails as a whole if the diagnostic
+/// list is non-empty once parsing has run to completion.
+pub fn parse_xml(
+    path: &PathBuf,
+    line: &str,
+    repair_profile: &StationProfile,
+    aoi_axi_profile: &StationProfile,
+) -> std::result::Result<Panel, Vec<Diagnostic>> {
     info!("Processing XML: {path:?}");
 
     let mut ret = Panel::default();
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+
+    let file = match std::fs::read_to_string(path) {
+        Ok(f) => f,
+        Err(e) => {
+            error!("Could not read file: {e}");
+            return Err(vec![Diagnostic {
+                path: path.clone(),
+                message: format!("Could not read file: {e}"),
+                row: 1,
+                col: 1,
+            }]);
+        }
+    };
 
-    let file = std::fs::read_to_string(path)?;
-    let xml = roxmltree::Document::parse(&file)?;
+    let xml = match roxmltree::Document::parse(&file) {
+        Ok(x) => x,
+        Err(e) => {
+            error!("Could not parse XML: {e}");
+            return Err(vec![Diagnostic {
+                path: path.clone(),
+                message: format!("Could not parse XML: {e}"),
+                row: 1,
+                col: 1,
+            }]);
+        }
+    };
 
     let root = xml.root_element();
     let mut repaired = false;
     let mut failed = false;
 
-    if let Some(ginfo) = root
+    let mut diag = |node: Node, message: String| {
+        let pos = xml.text_pos_at(node.range().start);
+        diagnostics.push(Diagnostic {
+            path: path.clone(),
+            message,
+            row: pos.row,
+            col: pos.col,
+        });
+    };
+
+    let ginfo = root
         .children()
-        .find(|f| f.has_tag_name("GlobalInformation"))
-    {
+        .find(|f| f.has_tag_name("GlobalInformation"));
+
+    if let Some(ginfo) = ginfo {
         for sub_child in ginfo.children().filter(|f| f.is_element()) {
             match sub_child.tag_name().name() {
                 /*"Station" => {
@@ -143,15 +302,20 @@ pub fn parse_xml(path: &PathBuf, line: &str) -> Result<Panel> {
         }
     } else {
         error!("Could not find <GlobalInformation>!");
-        bail!("Could not find <GlobalInformation>!");
+        diag(root, "Could not find <GlobalInformation>!".to_string());
     }
 
-    if ret.Program.is_empty()
-        || ret.Inspection_DT.year() < 2000
-        || (repaired && ret.Repair_DT.year() < 2000)
-    {
-        error!("Missing mandatory <GlobalInformation> elements!");
-        bail!("Missing mandatory <GlobalInformation> elements!");
+    if let Some(ginfo) = ginfo {
+        if ret.Program.is_empty()
+            || ret.Inspection_DT.year() < 2000
+            || (repaired && ret.Repair_DT.year() < 2000)
+        {
+            error!("Missing mandatory <GlobalInformation> elements!");
+            diag(
+                ginfo,
+                "Missing mandatory <GlobalInformation> elements!".to_string(),
+            );
+        }
     }
 
     if let Some(pcb_info) = root.children().find(|f| f.has_tag_name("PCBInformation")) {
@@ -188,15 +352,15 @@ pub fn parse_xml(path: &PathBuf, line: &str) -> Result<Panel> {
                 ret.Boards[i].Result = result;
             } else {
                 error!("SinglePCB sub-fields missing!");
-                bail!("SinglePCB sub-fields missing!");
+                diag(child, "SinglePCB sub-fields missing!".to_string());
             }
         }
-    }
 
-    for board in &ret.Boards {
-        if board.Serial_NMBR.is_empty() || board.Result.is_empty() {
-            error!("Board serial or result is missing!");
-            bail!("Board serial or result is missing!");
+        for board in &ret.Boards {
+            if board.Serial_NMBR.is_empty() || board.Result.is_empty() {
+                error!("Board serial or result is missing!");
+                diag(pcb_info, "Board serial or result is missing!".to_string());
+            }
         }
     }
 
@@ -238,28 +402,30 @@ pub fn parse_xml(path: &PathBuf, line: &str) -> Result<Panel> {
 
                     
                     if let Ok(x) = PCBNumber.parse::<usize>() {
-                        if let Some(board) = ret.Boards.get_mut(x) {
-                            if let Some(c) = WinID.rfind('-') {
-                                let split = WinID.split_at(c);
-                                WinID = split.0.to_string();
-                            }
-
-                            if Result != "Pszeudohiba" {
-                                if !board.Failed.contains(&WinID) {
-                                    board.Failed.push(WinID);
+                        if let Some(idx) = board_index(repair_profile.board_index_base, x) {
+                            if let Some(board) = ret.Boards.get_mut(idx) {
+                                trim_win_id(&mut WinID, repair_profile.win_id_trim);
+
+                                if Result != repair_profile.pseudo_marker {
+                                    if !board.Failed.contains(&WinID) {
+                                        board.Failed.push(WinID);
+                                    }
+                                } else if !board.Pseudo.contains(&WinID) {
+                                    board.Pseudo.push(WinID);
                                 }
-                            } else if !board.Pseudo.contains(&WinID) {
-                                board.Pseudo.push(WinID);
                             }
                         }
                     } else {
                         error!("Could not parse PCBNumber: {PCBNumber}");
-                        bail!("Could not parse PCBNumber: {PCBNumber}");
+                        diag(window, format!("Could not parse PCBNumber: {PCBNumber}"));
                     }
-                    
+
                 } else {
                     error!("Window interpreting error! WinID: {WinID}, PCBNumber: {PCBNumber}, Result: {Result}");
-                    bail!("Window interpreting error! WinID: {WinID}, PCBNumber: {PCBNumber}, Result: {Result}");
+                    diag(
+                        window,
+                        format!("Window interpreting error! WinID: {WinID}, PCBNumber: {PCBNumber}, Result: {Result}"),
+                    );
                 }
             }
         }
@@ -293,36 +459,41 @@ pub fn parse_xml(path: &PathBuf, line: &str) -> Result<Panel> {
                 }
 
                 if !(WinID.is_empty() || PCBNumber.is_empty() || Result.is_empty()) {
-                    if Result != "0" {
+                    if Result != aoi_axi_profile.pass_marker {
                         debug!(
                             "Window found! WinID: {WinID}, PCBNumber: {PCBNumber}, Result: {Result}"
                         );
 
                         if let Ok(x) = PCBNumber.parse::<usize>() {
-                            if x == 0 {
-                                error!("BoardNumber is 0. Was excepting 1+");
-                                bail!("BoardNumber is 0. Was excepting 1+");
-                            } else if let Some(board) = ret.Boards.get_mut(x - 1) {
-                                if let Some(c) = WinID.rfind('-') {
-                                    let split = WinID.split_at(c);
-                                    WinID = split.0.to_string();
+                            match board_index(aoi_axi_profile.board_index_base, x) {
+                                None => {
+                                    error!("BoardNumber is 0. Was excepting 1+");
+                                    diag(window, "BoardNumber is 0. Was excepting 1+".to_string());
                                 }
-
-                                if !board.Failed.contains(&WinID) {
-                                    board.Failed.push(WinID);
+                                Some(idx) => {
+                                    if let Some(board) = ret.Boards.get_mut(idx) {
+                                        trim_win_id(&mut WinID, aoi_axi_profile.win_id_trim);
+
+                                        if !board.Failed.contains(&WinID) {
+                                            board.Failed.push(WinID);
+                                        }
+                                    } else {
+                                        error!("Could not find board number {x}");
+                                        diag(window, format!("Could not find board number {x}"));
+                                    }
                                 }
-                            } else {
-                                error!("Could not find board number {x}");
-                                bail!("Could not find board number {x}");
                             }
                         } else {
                             error!("Could not parse PCBNumber: {PCBNumber}");
-                            bail!("Could not parse PCBNumber: {PCBNumber}");
+                            diag(window, format!("Could not parse PCBNumber: {PCBNumber}"));
                         }
                     }
                 } else {
                     error!("Window interpreting error! WinID: {WinID}, PCBNumber: {PCBNumber}, Result: {Result}");
-                    bail!("Window interpreting error! WinID: {WinID}, PCBNumber: {PCBNumber}, Result: {Result}");
+                    diag(
+                        window,
+                        format!("Window interpreting error! WinID: {WinID}, PCBNumber: {PCBNumber}, Result: {Result}"),
+                    );
                 }
             }
         }
@@ -338,11 +509,16 @@ pub fn parse_xml(path: &PathBuf, line: &str) -> Result<Panel> {
 
     // Set station name
     ret.Station = if repaired {
-        format!("{}_HARAN", line)
+        format!("{}{}", line, repair_profile.station_suffix)
     } else {
-        format!("{}_AOI_AXI", line)
+        format!("{}{}", line, aoi_axi_profile.station_suffix)
     };
 
+    if !diagnostics.is_empty() {
+        error!("Processing finished with {} diagnostic(s).", diagnostics.len());
+        return Err(diagnostics);
+    }
+
     info!("Processing OK.");
 
     Ok(ret)
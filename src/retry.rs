@@ -0,0 +1,199 @@
+// `serde` and `serde_json` are required, non-optional dependencies of this
+// crate (not behind an opt-in feature - `Panel`'s own derives in `panel.rs`
+// aren't gated either, so there's no build configuration where a failed
+// upload has nowhere durable to go), and `chrono` must have its `serde`
+// feature enabled for `NaiveDateTime` below to derive `Serialize`/`Deserialize`.
+use anyhow::Result;
+use chrono::{NaiveDateTime, Utc};
+use log::{debug, error};
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::SqlitePool;
+
+use crate::panel::{Board, Panel};
+
+/// Rows exceeding this many retry attempts are moved to the poison table
+/// for manual inspection instead of being retried forever.
+const MAX_ATTEMPTS: i64 = 8;
+/// Base backoff, doubled per attempt and capped at `MAX_BACKOFF_SECS`.
+const BASE_BACKOFF_SECS: i64 = 30;
+const MAX_BACKOFF_SECS: i64 = 3600;
+
+/// One board's worth of data flattened to the `SMT_AOI_RESULTS` row shape,
+/// so a row can be queued and retried independently of the `Panel` it came
+/// from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingRow {
+    pub serial_nmbr: String,
+    pub board_nmbr: usize,
+    pub program: String,
+    pub station: String,
+    pub operator: String,
+    pub result: String,
+    pub date_time: NaiveDateTime,
+    pub failed: String,
+    pub pseudo: String,
+}
+
+impl PendingRow {
+    /// Flattens every board of every panel into its own row, mirroring the
+    /// shape the uploader's `INSERT` builds.
+    pub fn from_panels(panels: &[Panel]) -> Vec<PendingRow> {
+        Self::from_entries(
+            &panels
+                .iter()
+                .flat_map(|panel| panel.Boards.iter().map(move |board| (panel, board)))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Same as [`PendingRow::from_panels`], but over already-flattened
+    /// `(Panel, Board)` pairs - lets a caller that only has a sub-slice of
+    /// boards left over from a partially-committed chunk build rows for
+    /// just those, instead of re-deriving every board of every panel.
+    pub fn from_entries(entries: &[(&Panel, &Board)]) -> Vec<PendingRow> {
+        entries
+            .iter()
+            .map(|(panel, board)| PendingRow {
+                serial_nmbr: board.Serial_NMBR.clone(),
+                board_nmbr: board.Board_NMBR,
+                program: panel.Program.clone(),
+                station: panel.Station.clone(),
+                operator: panel.Operator.clone(),
+                result: board.Result.clone(),
+                date_time: if panel.Operator.is_empty() {
+                    panel.Inspection_DT
+                } else {
+                    panel.Repair_DT
+                },
+                failed: board.Failed.join(", "),
+                pseudo: board.Pseudo.join(", "),
+            })
+            .collect()
+    }
+}
+
+/// Embedded SQLite dead-letter queue for rows that failed to upload, so a
+/// single bad chunk no longer forces the whole `last_date` window to be
+/// reprocessed, and rows that can never insert don't block progress
+/// forever.
+pub struct RetryQueue {
+    pool: SqlitePool,
+}
+
+impl RetryQueue {
+    pub async fn open(path: &str) -> Result<RetryQueue> {
+        let pool = SqlitePoolOptions::new()
+            .connect(&format!("sqlite://{path}?mode=rwc"))
+            .await?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS pending (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                row TEXT NOT NULL,
+                attempts INTEGER NOT NULL DEFAULT 0,
+                last_error TEXT,
+                next_retry_at INTEGER NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS poison (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                row TEXT NOT NULL,
+                attempts INTEGER NOT NULL,
+                last_error TEXT
+            )",
+        )
+        .execute(&pool)
+        .await?;
+
+        Ok(RetryQueue { pool })
+    }
+
+    /// Queues `rows` for retry after a failed upload, recording `error`.
+    pub async fn enqueue(&self, rows: &[PendingRow], error: &str) -> Result<()> {
+        let now = Utc::now().timestamp();
+
+        for row in rows {
+            let payload = serde_json::to_string(row)?;
+            sqlx::query(
+                "INSERT INTO pending (row, attempts, last_error, next_retry_at) VALUES (?, 0, ?, ?)",
+            )
+            .bind(payload)
+            .bind(error)
+            .bind(now)
+            .execute(&self.pool)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Drains every due row (per its exponential backoff), retrying it via
+    /// `apply`. Rows that fail again have their backoff doubled; rows
+    /// exceeding [`MAX_ATTEMPTS`] are moved to the poison table.
+    pub async fn drain<F, Fut>(&self, mut apply: F) -> Result<()>
+    where
+        F: FnMut(PendingRow) -> Fut,
+        Fut: std::future::Future<Output = Result<()>>,
+    {
+        let now = Utc::now().timestamp();
+        let rows: Vec<(i64, String, i64)> = sqlx::query_as(
+            "SELECT id, row, attempts FROM pending WHERE next_retry_at <= ? ORDER BY id",
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await?;
+
+        for (id, payload, attempts) in rows {
+            let row: PendingRow = serde_json::from_str(&payload)?;
+
+            match apply(row).await {
+                Ok(()) => {
+                    sqlx::query("DELETE FROM pending WHERE id = ?")
+                        .bind(id)
+                        .execute(&self.pool)
+                        .await?;
+                }
+                Err(e) => {
+                    let attempts = attempts + 1;
+
+                    if attempts >= MAX_ATTEMPTS {
+                        error!(
+                            "Row {id} exceeded {MAX_ATTEMPTS} retry attempts, moving to poison table: {e}"
+                        );
+                        sqlx::query(
+                            "INSERT INTO poison (row, attempts, last_error) VALUES (?, ?, ?)",
+                        )
+                        .bind(&payload)
+                        .bind(attempts)
+                        .bind(e.to_string())
+                        .execute(&self.pool)
+                        .await?;
+                        sqlx::query("DELETE FROM pending WHERE id = ?")
+                            .bind(id)
+                            .execute(&self.pool)
+                            .await?;
+                    } else {
+                        let backoff = (BASE_BACKOFF_SECS * (1 << attempts)).min(MAX_BACKOFF_SECS);
+                        debug!("Retry failed for row {id} (attempt {attempts}): {e}, backing off {backoff}s");
+                        sqlx::query(
+                            "UPDATE pending SET attempts = ?, last_error = ?, next_retry_at = ? WHERE id = ?",
+                        )
+                        .bind(attempts)
+                        .bind(e.to_string())
+                        .bind(now + backoff)
+                        .bind(id)
+                        .execute(&self.pool)
+                        .await?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+use anyhow::Result;
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+
+use crate::panel::Panel;
+
+/// One entry in the spool file: a parsed panel plus a monotonically
+/// increasing sequence id, so a partially-applied `restore` can resume
+/// without duplicating rows.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SpoolRecord {
+    pub seq: u64,
+    pub panel: Panel,
+}
+
+/// Appends `panels` to the line-delimited, self-describing record stream
+/// at `path`, one panel per line, continuing the sequence already present
+/// in the file. This is what keeps parsed results from being lost while
+/// the SQL backend is unreachable.
+pub fn dump(path: &Path, panels: Vec<Panel>) -> Result<()> {
+    let mut next_seq = last_sequence(path)?.map_or(0, |s| s + 1);
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    for panel in panels {
+        let record = SpoolRecord { seq: next_seq, panel };
+        writeln!(file, "{}", serde_json::to_string(&record)?)?;
+        next_seq += 1;
+    }
+
+    info!("Spooled chunk to {path:?}");
+    Ok(())
+}
+
+fn last_sequence(path: &Path) -> Result<Option<u64>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let mut last = None;
+    for line in BufReader::new(std::fs::File::open(path)?).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: SpoolRecord = serde_json::from_str(&line)?;
+        last = Some(record.seq);
+    }
+
+    Ok(last)
+}
+
+/// Reads the spool file back in order, skipping any record whose `seq` is
+/// not greater than `resume_after`, and hands each remaining panel (with its
+/// `seq`) to `apply` (the SQL insert). `apply` is responsible for durably
+/// persisting the `seq` it was given once its insert succeeds, so that if a
+/// later record fails and this function returns `Err`, everything already
+/// applied this run is not replayed on the next `restore`. Also returns the
+/// seq of the last record applied.
+pub async fn restore<F, Fut>(
+    path: &Path,
+    resume_after: Option<u64>,
+    mut apply: F,
+) -> Result<Option<u64>>
+where
+    F: FnMut(Panel, u64) -> Fut,
+    Fut: std::future::Future<Output = Result<()>>,
+{
+    if !path.exists() {
+        debug!("No spool file at {path:?}, nothing to restore");
+        return Ok(resume_after);
+    }
+
+    let mut last_applied = resume_after;
+
+    for line in BufReader::new(std::fs::File::open(path)?).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let record: SpoolRecord = serde_json::from_str(&line)?;
+        if resume_after.is_some_and(|r| record.seq <= r) {
+            continue;
+        }
+
+        apply(record.panel, record.seq).await?;
+        last_applied = Some(record.seq);
+    }
+
+    info!("Restore complete, last applied seq: {last_applied:?}");
+    Ok(last_applied)
+}
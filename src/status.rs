@@ -0,0 +1,97 @@
+use anyhow::Result;
+use bb8_redis::bb8::Pool;
+use bb8_redis::redis::AsyncCommands;
+use bb8_redis::RedisConnectionManager;
+use chrono::{DateTime, Local};
+use log::{debug, warn};
+use serde::Serialize;
+
+use crate::IconCollor;
+
+/// One status update published to the configured Redis channel, so a
+/// central dashboard can render every line's health without polling each
+/// uploader instance directly.
+#[derive(Debug, Serialize)]
+pub struct Heartbeat {
+    pub line: String,
+    pub icon: &'static str,
+    pub last_date: Option<String>,
+    pub rows_uploaded: usize,
+    pub last_error: Option<String>,
+    pub timestamp: String,
+}
+
+impl Heartbeat {
+    pub fn new(
+        line: &str,
+        icon: &IconCollor,
+        last_date: Option<DateTime<Local>>,
+        rows_uploaded: usize,
+        last_error: Option<String>,
+        timestamp: DateTime<Local>,
+    ) -> Heartbeat {
+        Heartbeat {
+            line: line.to_owned(),
+            icon: icon_name(icon),
+            last_date: last_date.map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string()),
+            rows_uploaded,
+            last_error,
+            timestamp: timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+}
+
+fn icon_name(icon: &IconCollor) -> &'static str {
+    match icon {
+        IconCollor::Green => "green",
+        IconCollor::Yellow => "yellow",
+        IconCollor::Red => "red",
+        IconCollor::Grey => "grey",
+        IconCollor::Purple => "purple",
+    }
+}
+
+/// Pooled publisher for the centralized-monitoring Redis channel. Entirely
+/// optional: if `[REDIS]` is absent from `config.ini`, a `StatusPublisher`
+/// is never constructed and installs without Redis behave as before.
+pub struct StatusPublisher {
+    pool: Pool<RedisConnectionManager>,
+    channel: String,
+}
+
+impl StatusPublisher {
+    pub async fn connect(url: &str, channel: &str) -> Result<StatusPublisher> {
+        let manager = RedisConnectionManager::new(url)?;
+        let pool = Pool::builder().build(manager).await?;
+
+        Ok(StatusPublisher {
+            pool,
+            channel: channel.to_owned(),
+        })
+    }
+
+    /// Publishes `heartbeat`, logging (but not propagating) any Redis
+    /// error - a monitoring outage must never block uploads.
+    pub async fn publish(&self, heartbeat: &Heartbeat) {
+        let payload = match serde_json::to_string(heartbeat) {
+            Ok(p) => p,
+            Err(e) => {
+                warn!("Failed to serialize heartbeat: {e}");
+                return;
+            }
+        };
+
+        let mut conn = match self.pool.get().await {
+            Ok(c) => c,
+            Err(e) => {
+                warn!("Failed to get Redis connection: {e}");
+                return;
+            }
+        };
+
+        match conn.publish::<_, _, ()>(&self.channel, payload).await {
+            Ok(()) => debug!("Published heartbeat for line {}", heartbeat.line),
+            Err(e) => warn!("Failed to publish heartbeat to Redis: {e}"),
+        }
+    }
+}